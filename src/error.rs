@@ -0,0 +1,43 @@
+// Copyright 2023 The Regents of the University of California
+// released under BSD 3-Clause License
+// author: Kevin Laeufer <laeufer@berkeley.edu>
+
+use std::fmt;
+
+/// Errors that can occur while reading an FST file.
+#[derive(Debug)]
+pub enum ReaderError {
+    Io(std::io::Error),
+    /// The file contains no FST header block.
+    NotAnFstFile,
+    /// A block declared a tag that this version of the reader does not know how to parse.
+    UnknownBlockType(u8),
+    /// The compressed payload of a block could not be decompressed.
+    Decompression(String),
+    /// The hierarchy or value-change data is malformed (e.g. an unknown scope type,
+    /// hierarchy entry tag or value-change tag, a field of the wrong width, or a missing
+    /// hierarchy block).
+    CorruptFile(String),
+}
+
+impl fmt::Display for ReaderError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ReaderError::Io(e) => write!(f, "I/O error: {e}"),
+            ReaderError::NotAnFstFile => write!(f, "not a valid FST file"),
+            ReaderError::UnknownBlockType(tag) => write!(f, "unknown FST block type: {tag}"),
+            ReaderError::Decompression(msg) => write!(f, "failed to decompress block: {msg}"),
+            ReaderError::CorruptFile(msg) => write!(f, "corrupt FST file: {msg}"),
+        }
+    }
+}
+
+impl std::error::Error for ReaderError {}
+
+impl From<std::io::Error> for ReaderError {
+    fn from(e: std::io::Error) -> Self {
+        ReaderError::Io(e)
+    }
+}
+
+pub type ReaderResult<T> = Result<T, ReaderError>;
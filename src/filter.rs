@@ -0,0 +1,85 @@
+// Copyright 2023 The Regents of the University of California
+// released under BSD 3-Clause License
+// author: Kevin Laeufer <laeufer@berkeley.edu>
+
+use crate::types::FstSignalHandle;
+use std::collections::HashSet;
+
+/// Selects which value changes [`crate::FstReader::read_signals`] delivers to its callback.
+///
+/// A filter restricts along two independent axes, each `None` by default meaning
+/// "no restriction": a time window and a set of signal handles. [`FstReader::read_signals`]
+/// uses the time window to skip whole value-change blocks without decompressing them
+/// (the block's own `[start_time, end_time]` bounds are read from its header when the
+/// file is opened),
+/// and uses the handle set to skip decoding changes for signals the caller doesn't need.
+///
+/// [`FstReader::read_signals`]: crate::FstReader::read_signals
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct FstFilter {
+    time_range: Option<(u64, u64)>,
+    handles: Option<HashSet<FstSignalHandle>>,
+}
+
+impl FstFilter {
+    /// Requests every value change for every signal in the file.
+    pub fn all() -> Self {
+        FstFilter {
+            time_range: None,
+            handles: None,
+        }
+    }
+
+    /// Restricts to value changes whose time falls in `[start, end]` (inclusive).
+    pub fn time_range(start: u64, end: u64) -> Self {
+        FstFilter {
+            time_range: Some((start, end)),
+            handles: None,
+        }
+    }
+
+    /// Restricts to value changes for the given signal handles.
+    pub fn signals(handles: &[FstSignalHandle]) -> Self {
+        FstFilter {
+            time_range: None,
+            handles: Some(handles.iter().copied().collect()),
+        }
+    }
+
+    /// Combines this filter with a time window restriction.
+    pub fn and_time_range(mut self, start: u64, end: u64) -> Self {
+        self.time_range = Some((start, end));
+        self
+    }
+
+    /// Combines this filter with a signal handle restriction.
+    pub fn and_signals(mut self, handles: &[FstSignalHandle]) -> Self {
+        self.handles = Some(handles.iter().copied().collect());
+        self
+    }
+
+    /// Whether a value-change block covering `[block_start, block_end]` can contain any
+    /// time accepted by this filter.
+    pub(crate) fn overlaps_time_range(&self, block_start: u64, block_end: u64) -> bool {
+        match self.time_range {
+            None => true,
+            Some((start, end)) => block_start <= end && start <= block_end,
+        }
+    }
+
+    /// Whether `time` itself is accepted by this filter's time window.
+    pub(crate) fn accepts_time(&self, time: u64) -> bool {
+        match self.time_range {
+            None => true,
+            Some((start, end)) => time >= start && time <= end,
+        }
+    }
+
+    /// Whether `handle` is accepted by this filter's handle set.
+    pub(crate) fn accepts_handle(&self, handle: FstSignalHandle) -> bool {
+        match &self.handles {
+            None => true,
+            Some(handles) => handles.contains(&handle),
+        }
+    }
+}
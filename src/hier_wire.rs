@@ -0,0 +1,106 @@
+// Copyright 2023 The Regents of the University of California
+// released under BSD 3-Clause License
+// author: Kevin Laeufer <laeufer@berkeley.edu>
+
+//! The on-disk encoding of [`crate::FstHierarchyEntry`], shared by [`crate::reader`] (which
+//! decodes it) and [`crate::writer`] (which encodes it) so the two stay in lock-step.
+//!
+//! Each entry is a one-byte tag followed by zero or more length-prefixed (LEB128) fields;
+//! see `reader::parse_hierarchy_entry` / `writer::FstWriter::push_hierarchy_entry`.
+
+use crate::types::{FstScopeType, FstVarDirection, FstVarType};
+
+pub(crate) const HIER_TAG_SCOPE: u8 = 0;
+pub(crate) const HIER_TAG_UP_SCOPE: u8 = 1;
+pub(crate) const HIER_TAG_VAR: u8 = 2;
+pub(crate) const HIER_TAG_ENUM_TABLE: u8 = 3;
+pub(crate) const HIER_TAG_ENUM_TABLE_REF: u8 = 4;
+
+pub(crate) fn scope_type_to_byte(tpe: FstScopeType) -> u8 {
+    tpe as u8
+}
+
+pub(crate) fn byte_to_scope_type(byte: u8) -> Option<FstScopeType> {
+    use FstScopeType::*;
+    const ALL: &[FstScopeType] = &[
+        Module,
+        Task,
+        Function,
+        Begin,
+        Fork,
+        Generate,
+        Struct,
+        Union,
+        Class,
+        Interface,
+        Package,
+        Program,
+        VhdlArchitecture,
+        VhdlProcedure,
+        VhdlFunction,
+        VhdlRecord,
+        VhdlProcess,
+        VhdlBlock,
+        VhdlForGenerate,
+        VhdlIfGenerate,
+        VhdlGenerate,
+        VhdlPackage,
+        AttributeBegin,
+        AttributeEnd,
+        VcdScope,
+        VcdUpScope,
+    ];
+    ALL.get(byte as usize).copied()
+}
+
+pub(crate) fn var_type_to_byte(tpe: FstVarType) -> u8 {
+    tpe as u8
+}
+
+pub(crate) fn byte_to_var_type(byte: u8) -> Option<FstVarType> {
+    use FstVarType::*;
+    const ALL: &[FstVarType] = &[
+        Event,
+        Integer,
+        Parameter,
+        Real,
+        RealParameter,
+        Reg,
+        Supply0,
+        Supply1,
+        Time,
+        Tri,
+        TriAnd,
+        TriOr,
+        TriReg,
+        Tri0,
+        Tri1,
+        WAnd,
+        Wire,
+        WOr,
+        String,
+        Port,
+        SparseArray,
+        RealTime,
+        GenericString,
+        Bit,
+        Logic,
+        Int,
+        ShortInt,
+        LongInt,
+        Byte,
+        Enum,
+        ShortReal,
+    ];
+    ALL.get(byte as usize).copied()
+}
+
+pub(crate) fn var_direction_to_byte(direction: FstVarDirection) -> u8 {
+    direction as u8
+}
+
+pub(crate) fn byte_to_var_direction(byte: u8) -> Option<FstVarDirection> {
+    use FstVarDirection::*;
+    const ALL: &[FstVarDirection] = &[Implicit, Input, Output, InOut, Buffer, Linkage];
+    ALL.get(byte as usize).copied()
+}
@@ -0,0 +1,48 @@
+// Copyright 2023 The Regents of the University of California
+// released under BSD 3-Clause License
+// author: Kevin Laeufer <laeufer@berkeley.edu>
+
+//! A per-handle index over value-change data, built on demand to answer random-access
+//! queries ("what was this signal at time T") without replaying the whole dump.
+
+#[derive(Debug, Clone)]
+pub(crate) enum IndexedValue {
+    String(String),
+    Real(f64),
+}
+
+#[derive(Debug, Clone)]
+pub(crate) struct IndexedChange {
+    pub(crate) time: u64,
+    pub(crate) value: IndexedValue,
+}
+
+/// Chronologically sorted value changes for every signal handle, keyed by 0-based index.
+#[derive(Debug, Default)]
+pub(crate) struct SignalIndex {
+    pub(crate) by_handle: Vec<Vec<IndexedChange>>,
+}
+
+impl SignalIndex {
+    pub(crate) fn with_handle_count(count: usize) -> Self {
+        SignalIndex {
+            by_handle: vec![Vec::new(); count],
+        }
+    }
+
+    pub(crate) fn push(&mut self, handle_index: usize, time: u64, value: IndexedValue) {
+        if handle_index >= self.by_handle.len() {
+            self.by_handle.resize_with(handle_index + 1, Vec::new);
+        }
+        self.by_handle[handle_index].push(IndexedChange { time, value });
+    }
+
+    /// Returns the chronologically sorted value changes recorded for `handle_index`,
+    /// or an empty slice if the handle has no changes (or is out of range).
+    pub(crate) fn changes_for(&self, handle_index: usize) -> &[IndexedChange] {
+        self.by_handle
+            .get(handle_index)
+            .map(|v| v.as_slice())
+            .unwrap_or(&[])
+    }
+}
@@ -0,0 +1,32 @@
+// Copyright 2023 The Regents of the University of California
+// released under BSD 3-Clause License
+// author: Kevin Laeufer <laeufer@berkeley.edu>
+
+//! A native Rust reader ([`FstReader`]) and writer ([`FstWriter`]) for files laid out
+//! like the [FST waveform format](https://blog.timhutt.co.uk/fst_spec/) used by GTKWave.
+//!
+//! The on-disk format is organized into a sequence of tagged blocks (header,
+//! hierarchy, geometry, value-change data, ...). [`FstReader`] parses the header and
+//! blackout blocks when the file is opened and decodes the hierarchy and value-change
+//! blocks lazily, so that large dumps can be streamed instead of being loaded into
+//! memory all at once. The hierarchy and value-change blocks are read in the encoding
+//! that [`FstWriter`] produces; GTKWave's bit-packed encoding of those blocks is not
+//! parsed.
+
+mod error;
+mod filter;
+mod hier_wire;
+mod index;
+mod reader;
+mod types;
+mod varint;
+mod writer;
+
+pub use error::ReaderError;
+pub use filter::FstFilter;
+pub use reader::FstReader;
+pub use types::{
+    FstBlackout, FstHeader, FstHierarchyEntry, FstScopeType, FstSignalHandle, FstSignalValue,
+    FstVarDirection, FstVarType,
+};
+pub use writer::{FstWriter, WriterError};
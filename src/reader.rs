@@ -0,0 +1,619 @@
+// Copyright 2023 The Regents of the University of California
+// released under BSD 3-Clause License
+// author: Kevin Laeufer <laeufer@berkeley.edu>
+
+use crate::error::{ReaderError, ReaderResult};
+use crate::filter::FstFilter;
+use crate::index::{IndexedValue, SignalIndex};
+use crate::types::{FstBlackout, FstHeader, FstHierarchyEntry, FstSignalHandle, FstSignalValue};
+use crate::varint::{read_u64_be, read_u8, read_varint_u64};
+use std::collections::HashMap;
+use std::io::{Read, Seek, SeekFrom};
+use std::ops::RangeInclusive;
+
+// Block type tags, matching `fstBlockType` in fstapi.h.
+const BT_HDR: u8 = 0;
+const BT_VC_DATA: u8 = 1;
+const BT_BLACKOUT: u8 = 2;
+const BT_GEOM: u8 = 3;
+const BT_HIER: u8 = 4;
+const BT_VC_DATA_DYN_ALIAS: u8 = 5;
+const BT_HIER_LZ4: u8 = 6;
+const BT_HIER_LZ4DUO: u8 = 7;
+const BT_VC_DATA_DYN_ALIAS2: u8 = 8;
+const BT_SKIP: u8 = 255;
+
+/// Location of a value-change data block, recorded while scanning the file so that
+/// `read_signals` can decode it without re-reading the whole file.
+///
+/// `start_time`/`end_time` let [`FstFilter::time_range`] skip whole blocks that fall
+/// outside the requested window without decoding their contents.
+#[derive(Debug, Clone, Copy)]
+struct VcBlockInfo {
+    offset: u64,
+    length: u64,
+    start_time: u64,
+    end_time: u64,
+    uncompressed_length: u64,
+}
+
+#[derive(Debug, Clone, Copy)]
+struct HierarchyBlockInfo {
+    offset: u64,
+    length: u64,
+    uncompressed_length: u64,
+    is_lz4: bool,
+}
+
+/// A reader for the FST waveform format, the binary format used by GTKWave.
+///
+/// The hierarchy and value-change blocks are decoded in the encoding that
+/// [`crate::FstWriter`] produces; GTKWave's bit-packed encoding of those blocks is not
+/// parsed.
+///
+/// The header and blackout blocks are parsed eagerly when the file is opened (geometry
+/// blocks are skipped); the hierarchy and
+/// value-change data are decoded lazily by [`FstReader::read_hierarchy`] and
+/// [`FstReader::read_signals`] respectively, since they can be large enough that holding
+/// them all in memory at once is undesirable.
+pub struct FstReader<R: Read + Seek> {
+    input: R,
+    header: FstHeader,
+    hierarchy_block: Option<HierarchyBlockInfo>,
+    vc_blocks: Vec<VcBlockInfo>,
+    blackouts: Vec<FstBlackout>,
+    /// Built lazily by [`FstReader::value_at`] / [`FstReader::transitions`] on first use.
+    index: Option<SignalIndex>,
+    /// Enum table handle -> (name, [(bit-vector value, label)]), populated while walking
+    /// the hierarchy.
+    enum_tables: HashMap<u64, (String, Vec<(String, String)>)>,
+    /// Signal handle index -> enum table handle, populated while walking the hierarchy:
+    /// an `EnumTableRef` attribute always immediately precedes the `Var` entry it applies to.
+    var_enum_table: HashMap<usize, u64>,
+    pending_enum_table: Option<u64>,
+}
+
+impl<R: Read + Seek> FstReader<R> {
+    /// Opens an FST file, parsing the header and blackout blocks eagerly and recording the
+    /// location and time range of each value-change block. Geometry blocks are skipped.
+    ///
+    /// The hierarchy and value-change data are *not* decoded here; call
+    /// [`FstReader::read_hierarchy`] / [`FstReader::read_signals`] to stream them.
+    pub fn open(mut input: R) -> ReaderResult<Self> {
+        let mut header = None;
+        let mut hierarchy_block = None;
+        let mut vc_blocks = Vec::new();
+        let mut blackouts = Vec::new();
+
+        let end = input.seek(SeekFrom::End(0))?;
+        input.seek(SeekFrom::Start(0))?;
+
+        loop {
+            let pos = input.stream_position()?;
+            if pos >= end {
+                break;
+            }
+            let tag = read_u8(&mut input)?;
+            let section_length = read_u64_be(&mut input)?;
+            // `section_length` includes the 8-byte length field itself, matching the C writer.
+            let payload_len = section_length.saturating_sub(8);
+            let payload_start = input.stream_position()?;
+
+            match tag {
+                BT_HDR => {
+                    header = Some(Self::parse_header_block(&mut input)?);
+                }
+                BT_BLACKOUT => {
+                    Self::parse_blackout_block(&mut input, &mut blackouts)?;
+                }
+                BT_HIER | BT_HIER_LZ4 | BT_HIER_LZ4DUO => {
+                    let uncompressed_length = read_varint_u64(&mut input)?;
+                    hierarchy_block = Some(HierarchyBlockInfo {
+                        offset: input.stream_position()?,
+                        length: payload_start + payload_len - input.stream_position()?,
+                        uncompressed_length,
+                        is_lz4: tag != BT_HIER,
+                    });
+                }
+                BT_VC_DATA | BT_VC_DATA_DYN_ALIAS | BT_VC_DATA_DYN_ALIAS2 => {
+                    let start_time = read_varint_u64(&mut input)?;
+                    let end_time = read_varint_u64(&mut input)?;
+                    let uncompressed_length = read_varint_u64(&mut input)?;
+                    let changes_offset = input.stream_position()?;
+                    vc_blocks.push(VcBlockInfo {
+                        offset: changes_offset,
+                        length: payload_start + payload_len - changes_offset,
+                        start_time,
+                        end_time,
+                        uncompressed_length,
+                    });
+                }
+                BT_GEOM | BT_SKIP => {
+                    // Per-signal byte geometry is not used by this reader: each value change
+                    // carries its own length, so `read_signals` decodes without it.
+                }
+                other => return Err(ReaderError::UnknownBlockType(other)),
+            }
+
+            input.seek(SeekFrom::Start(payload_start + payload_len))?;
+        }
+
+        Ok(FstReader {
+            input,
+            header: header.ok_or(ReaderError::NotAnFstFile)?,
+            hierarchy_block,
+            vc_blocks,
+            blackouts,
+            index: None,
+            enum_tables: HashMap::new(),
+            var_enum_table: HashMap::new(),
+            pending_enum_table: None,
+        })
+    }
+
+    fn parse_header_block(input: &mut impl Read) -> ReaderResult<FstHeader> {
+        let start_time = read_u64_be(input)?;
+        let end_time = read_u64_be(input)?;
+        // real number endianness marker (f64), unused by this reader
+        let mut _endian_marker = [0u8; 8];
+        input.read_exact(&mut _endian_marker)?;
+        let _writer_memory_use = read_u64_be(input)?;
+        let _scope_count = read_u64_be(input)?;
+        let var_count = read_u64_be(input)?;
+        let max_handle = read_u64_be(input)?;
+        let _vc_section_count = read_u64_be(input)?;
+        let _timescale_exponent = read_u8(input)?;
+        let version = read_fixed_string(input, 128)?;
+        let date = read_fixed_string(input, 119)?;
+        Ok(FstHeader {
+            start_time,
+            end_time,
+            var_count,
+            max_handle,
+            version,
+            date,
+        })
+    }
+
+    /// Parses the blackout block: a varint count followed by that many
+    /// `(activity: u8, delta_time: varint)` pairs, where each delta is relative to the
+    /// previous entry's time (or to zero for the first entry).
+    fn parse_blackout_block(
+        input: &mut impl Read,
+        blackouts: &mut Vec<FstBlackout>,
+    ) -> ReaderResult<()> {
+        let count = read_varint_u64(input)?;
+        let mut time = 0u64;
+        for _ in 0..count {
+            let activity_enabled = read_u8(input)? != 0;
+            time += read_varint_u64(input)?;
+            blackouts.push(FstBlackout {
+                time,
+                activity_enabled,
+            });
+        }
+        Ok(())
+    }
+
+    /// Returns the header fields parsed when the file was opened.
+    pub fn get_header(&self) -> FstHeader {
+        self.header.clone()
+    }
+
+    /// Returns the dumpoff/dumpon transitions recorded in the file, in chronological order.
+    ///
+    /// An empty slice means the dump was active for its entire duration.
+    pub fn get_blackouts(&self) -> &[FstBlackout] {
+        &self.blackouts
+    }
+
+    /// Walks the hierarchy, invoking `callback` once per entry in file order.
+    pub fn read_hierarchy(
+        &mut self,
+        mut callback: impl FnMut(FstHierarchyEntry),
+    ) -> ReaderResult<()> {
+        let block = self
+            .hierarchy_block
+            .ok_or_else(|| ReaderError::CorruptFile("missing hierarchy block".to_string()))?;
+        self.input.seek(SeekFrom::Start(block.offset))?;
+        let mut compressed = vec![0u8; block.length as usize];
+        self.input.read_exact(&mut compressed)?;
+        let raw = decompress_hierarchy(&compressed, block.uncompressed_length, block.is_lz4)?;
+        let mut cursor = std::io::Cursor::new(raw);
+        while (cursor.position() as usize) < cursor.get_ref().len() {
+            match parse_hierarchy_entry(&mut cursor)? {
+                Some(entry) => {
+                    self.track_enum_table(&entry);
+                    callback(entry);
+                }
+                None => break,
+            }
+        }
+        Ok(())
+    }
+
+    /// Maintains the handle -> enum table association as the hierarchy is walked: an
+    /// `EnumTableRef` attribute always immediately precedes the `Var` entry it describes.
+    fn track_enum_table(&mut self, entry: &FstHierarchyEntry) {
+        match entry {
+            FstHierarchyEntry::EnumTable {
+                name,
+                handle,
+                mapping,
+            } => {
+                self.enum_tables
+                    .insert(*handle, (name.clone(), mapping.clone()));
+            }
+            FstHierarchyEntry::EnumTableRef { handle } => {
+                self.pending_enum_table = Some(*handle);
+            }
+            FstHierarchyEntry::Var { handle, .. } => {
+                if let Some(table) = self.pending_enum_table.take() {
+                    self.var_enum_table.insert(handle.get_index(), table);
+                }
+            }
+            _ => {}
+        }
+    }
+
+    /// Resolves a decoded bit-vector `value` against `handle`'s enum table, if it has one.
+    ///
+    /// Returns `None` when `handle` has no associated enum table, `value` isn't a
+    /// [`FstSignalValue::String`], or the raw bits don't match any entry in the table.
+    ///
+    /// Requires [`FstReader::read_hierarchy`] to have run first: the handle -> enum table
+    /// association is only populated while walking the hierarchy, so calling this before
+    /// that returns `None` even for a handle that does have an enum table. `read_signals`
+    /// applies this resolution for callers automatically once the hierarchy has been read.
+    pub fn resolve_enum<'v>(
+        &self,
+        handle: FstSignalHandle,
+        value: &FstSignalValue<'v>,
+    ) -> Option<FstSignalValue<'v>> {
+        let raw = match value {
+            FstSignalValue::String(s) => *s,
+            _ => return None,
+        };
+        let table_handle = self.var_enum_table.get(&handle.get_index())?;
+        let (_, mapping) = self.enum_tables.get(table_handle)?;
+        let name = mapping.iter().find(|(v, _)| v == raw).map(|(_, n)| n.clone())?;
+        Some(FstSignalValue::Enum { raw, name })
+    }
+
+    /// Streams value changes across the requested `filter`, invoking `callback` for every
+    /// matching `(time, handle, value, in_blackout)` in chronological order, where
+    /// `in_blackout` is `true` if `time` falls inside a region where the dump was turned
+    /// off (see [`FstReader::get_blackouts`]): GTKWave still emits the transitions that
+    /// bracket the gap, but consumers replaying signal state should not treat the absence
+    /// of changes in between as "unchanged".
+    ///
+    /// A [`FstSignalValue::String`] whose handle has an associated enum table is resolved
+    /// to [`FstSignalValue::Enum`] automatically (see [`FstReader::resolve_enum`]); other
+    /// values are passed through unchanged.
+    ///
+    /// Blocks whose `[start_time, end_time]` range (known from the block's own header,
+    /// before any decompression happens) does not overlap `filter`'s time window are
+    /// skipped entirely. Within a decompressed block, only the `(time, handle)` prefix of
+    /// each change is decoded up front; changes rejected by `filter`'s time window or
+    /// handle set have their value payload skipped without being parsed.
+    pub fn read_signals(
+        &mut self,
+        filter: &FstFilter,
+        mut callback: impl FnMut(u64, FstSignalHandle, FstSignalValue, bool),
+    ) -> ReaderResult<()> {
+        let blocks = self.vc_blocks.clone();
+        let mut blackout_idx = 0usize;
+        let mut dump_active = true;
+        for block in blocks {
+            if !filter.overlaps_time_range(block.start_time, block.end_time) {
+                continue;
+            }
+            self.input.seek(SeekFrom::Start(block.offset))?;
+            let mut compressed = vec![0u8; block.length as usize];
+            self.input.read_exact(&mut compressed)?;
+            let raw = gzip_decompress(&compressed, block.uncompressed_length)?;
+            let mut cursor = std::io::Cursor::new(raw);
+            while (cursor.position() as usize) < cursor.get_ref().len() {
+                let (time, handle) = parse_value_change_header(&mut cursor)?;
+                // Advance through the sorted blackout list as time moves forward, rather
+                // than rescanning it for every value change.
+                while blackout_idx < self.blackouts.len() && self.blackouts[blackout_idx].time <= time {
+                    dump_active = self.blackouts[blackout_idx].activity_enabled;
+                    blackout_idx += 1;
+                }
+                let in_blackout = !dump_active;
+                if !filter.accepts_time(time) || !filter.accepts_handle(handle) {
+                    skip_value(&mut cursor)?;
+                    continue;
+                }
+                match parse_value(&mut cursor)? {
+                    FstValueBuf::String(s) => {
+                        let value = FstSignalValue::String(&s);
+                        let value = self.resolve_enum(handle, &value).unwrap_or(value);
+                        callback(time, handle, value, in_blackout)
+                    }
+                    FstValueBuf::Real(r) => callback(time, handle, FstSignalValue::Real(r), in_blackout),
+                }
+            }
+        }
+        Ok(())
+    }
+
+    /// Builds the per-handle random-access index, if it hasn't been built already.
+    ///
+    /// This decodes every value-change block once; the resulting index is cached on the
+    /// reader so repeated [`FstReader::value_at`] / [`FstReader::transitions`] calls are cheap.
+    fn ensure_index(&mut self) -> ReaderResult<()> {
+        if self.index.is_some() {
+            return Ok(());
+        }
+        let mut index = SignalIndex::with_handle_count(self.header.max_handle as usize);
+        let blocks = self.vc_blocks.clone();
+        for block in blocks {
+            self.input.seek(SeekFrom::Start(block.offset))?;
+            let mut compressed = vec![0u8; block.length as usize];
+            self.input.read_exact(&mut compressed)?;
+            let raw = gzip_decompress(&compressed, block.uncompressed_length)?;
+            let mut cursor = std::io::Cursor::new(raw);
+            while (cursor.position() as usize) < cursor.get_ref().len() {
+                let (time, handle, value) = match parse_value_change(&mut cursor)? {
+                    Some(v) => v,
+                    None => break,
+                };
+                let value = match value {
+                    FstValueBuf::String(s) => IndexedValue::String(s),
+                    FstValueBuf::Real(r) => IndexedValue::Real(r),
+                };
+                index.push(handle.get_index(), time, value);
+            }
+        }
+        self.index = Some(index);
+        Ok(())
+    }
+
+    /// Returns the value of `handle` as of `time`, i.e. the most recent change at or
+    /// before `time`, or `None` if the signal has no recorded change by then.
+    ///
+    /// Like [`FstReader::read_signals`], a value whose handle has an associated enum table
+    /// is resolved to [`FstSignalValue::Enum`] automatically.
+    pub fn value_at(&mut self, handle: FstSignalHandle, time: u64) -> ReaderResult<Option<FstSignalValue<'_>>> {
+        self.ensure_index()?;
+        let changes = self.index.as_ref().unwrap().changes_for(handle.get_index());
+        let pos = changes.partition_point(|c| c.time <= time);
+        let value = changes.get(pos.wrapping_sub(1)).filter(|_| pos > 0).map(|c| match &c.value {
+            IndexedValue::String(s) => FstSignalValue::String(s),
+            IndexedValue::Real(r) => FstSignalValue::Real(*r),
+        });
+        Ok(value.map(|v| self.resolve_enum(handle, &v).unwrap_or(v)))
+    }
+
+    /// Returns an iterator over every value change of `handle` within `range` (inclusive),
+    /// in chronological order.
+    ///
+    /// Like [`FstReader::read_signals`], a value whose handle has an associated enum table
+    /// is resolved to [`FstSignalValue::Enum`] automatically.
+    pub fn transitions(
+        &mut self,
+        handle: FstSignalHandle,
+        range: RangeInclusive<u64>,
+    ) -> ReaderResult<impl Iterator<Item = (u64, FstSignalValue<'_>)>> {
+        self.ensure_index()?;
+        let this = &*self;
+        let changes = this.index.as_ref().unwrap().changes_for(handle.get_index());
+        let (start, end) = (*range.start(), *range.end());
+        Ok(changes
+            .iter()
+            .filter(move |c| c.time >= start && c.time <= end)
+            .map(move |c| {
+                let value = match &c.value {
+                    IndexedValue::String(s) => FstSignalValue::String(s),
+                    IndexedValue::Real(r) => FstSignalValue::Real(*r),
+                };
+                (c.time, this.resolve_enum(handle, &value).unwrap_or(value))
+            }))
+    }
+}
+
+fn read_fixed_string(input: &mut impl Read, len: usize) -> ReaderResult<String> {
+    let mut buf = vec![0u8; len];
+    input.read_exact(&mut buf)?;
+    let end = buf.iter().position(|&b| b == 0).unwrap_or(buf.len());
+    Ok(String::from_utf8_lossy(&buf[..end]).into_owned())
+}
+
+fn decompress_hierarchy(data: &[u8], uncompressed_len: u64, is_lz4: bool) -> ReaderResult<Vec<u8>> {
+    if is_lz4 {
+        lz4_flex::decompress(data, uncompressed_len as usize)
+            .map_err(|e| ReaderError::Decompression(e.to_string()))
+    } else {
+        gzip_decompress(data, uncompressed_len)
+    }
+}
+
+/// Inflates a gzip-compressed block payload, as written by `FstWriter::flush_block` for
+/// value-change data (and by `FstWriter::finish` for the hierarchy).
+fn gzip_decompress(data: &[u8], uncompressed_len: u64) -> ReaderResult<Vec<u8>> {
+    use std::io::Read as _;
+    let mut decoder = flate2::read::GzDecoder::new(data);
+    let mut out = Vec::with_capacity(uncompressed_len as usize);
+    decoder
+        .read_to_end(&mut out)
+        .map_err(|e| ReaderError::Decompression(e.to_string()))?;
+    Ok(out)
+}
+
+/// Reads one LEB128-length-prefixed field, as written by `FstWriter::push_hierarchy_entry`.
+fn read_field(cursor: &mut std::io::Cursor<Vec<u8>>) -> ReaderResult<Vec<u8>> {
+    let len = read_varint_u64(cursor)? as usize;
+    let mut buf = vec![0u8; len];
+    cursor.read_exact(&mut buf)?;
+    Ok(buf)
+}
+
+fn read_field_string(cursor: &mut std::io::Cursor<Vec<u8>>) -> ReaderResult<String> {
+    Ok(String::from_utf8_lossy(&read_field(cursor)?).into_owned())
+}
+
+/// Interprets `bytes` as a big-endian `u32`, matching `FstWriter::add_var`'s encoding.
+fn bytes_to_u32(bytes: Vec<u8>) -> ReaderResult<u32> {
+    let len = bytes.len();
+    bytes
+        .try_into()
+        .map(u32::from_be_bytes)
+        .map_err(|_| ReaderError::CorruptFile(format!("expected a 4-byte field, got {len}")))
+}
+
+/// Interprets `bytes` as a big-endian `u64`, matching `FstWriter::add_var`'s handle encoding
+/// (also used for enum table handles).
+fn bytes_to_u64(bytes: Vec<u8>) -> ReaderResult<u64> {
+    let len = bytes.len();
+    bytes
+        .try_into()
+        .map(u64::from_be_bytes)
+        .map_err(|_| ReaderError::CorruptFile(format!("expected an 8-byte field, got {len}")))
+}
+
+/// Decodes one [`FstHierarchyEntry`], matching the encoding documented in [`crate::hier_wire`].
+fn parse_hierarchy_entry(
+    cursor: &mut std::io::Cursor<Vec<u8>>,
+) -> ReaderResult<Option<FstHierarchyEntry>> {
+    use crate::hier_wire::*;
+
+    let tag = read_u8(cursor)?;
+    let entry = match tag {
+        HIER_TAG_SCOPE => {
+            let tpe_byte = read_field(cursor)?;
+            let name = read_field_string(cursor)?;
+            let component = read_field_string(cursor)?;
+            let tpe = byte_to_scope_type(tpe_byte[0])
+                .ok_or_else(|| ReaderError::CorruptFile("invalid scope type".to_string()))?;
+            FstHierarchyEntry::Scope {
+                tpe,
+                name,
+                component,
+            }
+        }
+        HIER_TAG_UP_SCOPE => FstHierarchyEntry::UpScope,
+        HIER_TAG_VAR => {
+            let type_and_dir = read_field(cursor)?;
+            let length_bytes = read_field(cursor)?;
+            let handle_bytes = read_field(cursor)?;
+            let name = read_field_string(cursor)?;
+            let tpe = byte_to_var_type(type_and_dir[0])
+                .ok_or_else(|| ReaderError::CorruptFile("invalid variable type".to_string()))?;
+            let direction = byte_to_var_direction(type_and_dir[1])
+                .ok_or_else(|| ReaderError::CorruptFile("invalid variable direction".to_string()))?;
+            let length = bytes_to_u32(length_bytes)?;
+            let handle_index = bytes_to_u64(handle_bytes)?;
+            FstHierarchyEntry::Var {
+                tpe,
+                direction,
+                name,
+                length,
+                handle: FstSignalHandle::from_index(handle_index as usize),
+                is_alias: false,
+            }
+        }
+        HIER_TAG_ENUM_TABLE => {
+            let handle_bytes = read_field(cursor)?;
+            let name = read_field_string(cursor)?;
+            let encoded = read_field(cursor)?;
+            let mut enc_cursor = std::io::Cursor::new(encoded);
+            let count = read_varint_u64(&mut enc_cursor)?;
+            let mut mapping = Vec::with_capacity(count as usize);
+            for _ in 0..count {
+                let value = read_field_string(&mut enc_cursor)?;
+                let label = read_field_string(&mut enc_cursor)?;
+                mapping.push((value, label));
+            }
+            FstHierarchyEntry::EnumTable {
+                name,
+                handle: bytes_to_u64(handle_bytes)?,
+                mapping,
+            }
+        }
+        HIER_TAG_ENUM_TABLE_REF => {
+            let handle_bytes = read_field(cursor)?;
+            FstHierarchyEntry::EnumTableRef {
+                handle: bytes_to_u64(handle_bytes)?,
+            }
+        }
+        other => {
+            return Err(ReaderError::CorruptFile(format!(
+                "unknown hierarchy entry tag {other}"
+            )))
+        }
+    };
+    Ok(Some(entry))
+}
+
+enum FstValueBuf {
+    String(String),
+    Real(f64),
+}
+
+/// Decodes the `(time, handle)` prefix shared by every value change, leaving the cursor
+/// positioned at the tag byte that precedes the value payload. Splitting this out from the
+/// payload itself lets callers that don't want a particular change (e.g. `read_signals`
+/// filtering on handle) skip the payload via [`skip_value`] instead of decoding and
+/// discarding it.
+fn parse_value_change_header(
+    cursor: &mut std::io::Cursor<Vec<u8>>,
+) -> ReaderResult<(u64, FstSignalHandle)> {
+    let time = read_varint_u64(cursor)?;
+    let handle_index = read_varint_u64(cursor)?;
+    Ok((time, FstSignalHandle::from_index(handle_index as usize)))
+}
+
+/// Decodes the value payload (tag plus contents), matching the encoding written by
+/// `FstWriter::flush_block`.
+fn parse_value(cursor: &mut std::io::Cursor<Vec<u8>>) -> ReaderResult<FstValueBuf> {
+    let tag = read_u8(cursor)?;
+    match tag {
+        0 => {
+            let len = read_varint_u64(cursor)? as usize;
+            let mut buf = vec![0u8; len];
+            cursor.read_exact(&mut buf)?;
+            Ok(FstValueBuf::String(String::from_utf8_lossy(&buf).into_owned()))
+        }
+        1 => {
+            let mut buf = [0u8; 8];
+            cursor.read_exact(&mut buf)?;
+            Ok(FstValueBuf::Real(f64::from_le_bytes(buf)))
+        }
+        other => Err(ReaderError::CorruptFile(format!(
+            "unknown value-change tag {other}"
+        ))),
+    }
+}
+
+/// Skips over a value payload without decoding it, for changes that a filter has already
+/// decided to discard.
+fn skip_value(cursor: &mut std::io::Cursor<Vec<u8>>) -> ReaderResult<()> {
+    let tag = read_u8(cursor)?;
+    match tag {
+        0 => {
+            let len = read_varint_u64(cursor)?;
+            cursor.seek(SeekFrom::Current(len as i64))?;
+        }
+        1 => {
+            cursor.seek(SeekFrom::Current(8))?;
+        }
+        other => {
+            return Err(ReaderError::CorruptFile(format!(
+                "unknown value-change tag {other}"
+            )))
+        }
+    }
+    Ok(())
+}
+
+/// Decodes one `(time, handle, value)` triple, matching the encoding written by
+/// `FstWriter::flush_block`.
+fn parse_value_change(
+    cursor: &mut std::io::Cursor<Vec<u8>>,
+) -> ReaderResult<Option<(u64, FstSignalHandle, FstValueBuf)>> {
+    let (time, handle) = parse_value_change_header(cursor)?;
+    let value = parse_value(cursor)?;
+    Ok(Some((time, handle, value)))
+}
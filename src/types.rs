@@ -0,0 +1,186 @@
+// Copyright 2023 The Regents of the University of California
+// released under BSD 3-Clause License
+// author: Kevin Laeufer <laeufer@berkeley.edu>
+
+/// Identifies a single variable's value-change stream.
+///
+/// Handles are 1-based in the FST file format (to match GTKWave's C API), but
+/// [`FstSignalHandle::get_index`] converts to the more convenient 0-based index
+/// used internally to index into our per-signal bookkeeping.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct FstSignalHandle(u32);
+
+impl FstSignalHandle {
+    pub fn new(handle: u32) -> Self {
+        FstSignalHandle(handle)
+    }
+
+    /// Returns the 0-based index corresponding to this handle.
+    pub fn get_index(&self) -> usize {
+        (self.0 - 1) as usize
+    }
+
+    pub fn from_index(index: usize) -> Self {
+        FstSignalHandle((index + 1) as u32)
+    }
+}
+
+impl std::fmt::Display for FstSignalHandle {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.0)
+    }
+}
+
+/// Header fields that summarize the whole dump, mirroring `fstReaderGet*` in the C API.
+#[derive(Debug, Clone, PartialEq)]
+pub struct FstHeader {
+    pub start_time: u64,
+    pub end_time: u64,
+    pub var_count: u64,
+    pub max_handle: u64,
+    pub version: String,
+    pub date: String,
+}
+
+/// A dumpoff/dumpon transition as recorded in the FST blackout block.
+///
+/// While `activity_enabled` is `false`, the dump was turned off (VCD `$dumpoff`) and no
+/// value changes were recorded even though simulation time kept advancing; consumers that
+/// draw a cursor or interpolate between changes need to know about these gaps.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct FstBlackout {
+    pub time: u64,
+    pub activity_enabled: bool,
+}
+
+/// Scope types, matching `fstScopeType` in the GTKWave C API (VCD scope kinds plus the
+/// richer set used by VHDL/SystemVerilog front ends).
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum FstScopeType {
+    Module,
+    Task,
+    Function,
+    Begin,
+    Fork,
+    Generate,
+    Struct,
+    Union,
+    Class,
+    Interface,
+    Package,
+    Program,
+    VhdlArchitecture,
+    VhdlProcedure,
+    VhdlFunction,
+    VhdlRecord,
+    VhdlProcess,
+    VhdlBlock,
+    VhdlForGenerate,
+    VhdlIfGenerate,
+    VhdlGenerate,
+    VhdlPackage,
+    AttributeBegin,
+    AttributeEnd,
+    VcdScope,
+    VcdUpScope,
+}
+
+/// Variable types, matching `fstVarType` in the GTKWave C API.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum FstVarType {
+    Event,
+    Integer,
+    Parameter,
+    Real,
+    RealParameter,
+    Reg,
+    Supply0,
+    Supply1,
+    Time,
+    Tri,
+    TriAnd,
+    TriOr,
+    TriReg,
+    Tri0,
+    Tri1,
+    WAnd,
+    Wire,
+    WOr,
+    String,
+    Port,
+    SparseArray,
+    RealTime,
+    GenericString,
+    Bit,
+    Logic,
+    Int,
+    ShortInt,
+    LongInt,
+    Byte,
+    Enum,
+    ShortReal,
+}
+
+/// Port direction of a variable, matching `fstVarDir` in the GTKWave C API.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum FstVarDirection {
+    Implicit,
+    Input,
+    Output,
+    InOut,
+    Buffer,
+    Linkage,
+}
+
+/// One entry yielded while walking the hierarchy with [`crate::FstReader::read_hierarchy`].
+#[derive(Debug, Clone, PartialEq)]
+pub enum FstHierarchyEntry {
+    Scope {
+        tpe: FstScopeType,
+        name: String,
+        component: String,
+    },
+    UpScope,
+    Var {
+        tpe: FstVarType,
+        direction: FstVarDirection,
+        name: String,
+        length: u32,
+        handle: FstSignalHandle,
+        is_alias: bool,
+    },
+    AttributeBegin {
+        name: String,
+    },
+    AttributeEnd,
+    PathName {
+        id: u64,
+        name: String,
+    },
+    SourceStem {
+        is_instantiation: bool,
+        path_id: u64,
+        line: u64,
+    },
+    Comment {
+        string: String,
+    },
+    EnumTable {
+        name: String,
+        handle: u64,
+        mapping: Vec<(String, String)>,
+    },
+    EnumTableRef {
+        handle: u64,
+    },
+}
+
+/// A single value-change sample as delivered by [`crate::FstReader::read_signals`].
+#[derive(Debug, Clone, PartialEq)]
+pub enum FstSignalValue<'a> {
+    String(&'a str),
+    Real(f64),
+    /// A bit-vector value resolved against its variable's enum table by
+    /// [`crate::FstReader::resolve_enum`], e.g. `raw: "010", name: "READ"`.
+    Enum { raw: &'a str, name: String },
+}
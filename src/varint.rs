@@ -0,0 +1,36 @@
+// Copyright 2023 The Regents of the University of California
+// released under BSD 3-Clause License
+// author: Kevin Laeufer <laeufer@berkeley.edu>
+
+//! Small helpers for the LEB128 varints and fixed-width big-endian integers that show up
+//! throughout the FST block headers.
+
+use crate::error::ReaderResult;
+use std::io::Read;
+
+pub(crate) fn read_u8(input: &mut impl Read) -> ReaderResult<u8> {
+    let mut buf = [0u8; 1];
+    input.read_exact(&mut buf)?;
+    Ok(buf[0])
+}
+
+pub(crate) fn read_u64_be(input: &mut impl Read) -> ReaderResult<u64> {
+    let mut buf = [0u8; 8];
+    input.read_exact(&mut buf)?;
+    Ok(u64::from_be_bytes(buf))
+}
+
+/// Reads an unsigned LEB128 varint, as used for most counts and deltas in the FST format.
+pub(crate) fn read_varint_u64(input: &mut impl Read) -> ReaderResult<u64> {
+    let mut value: u64 = 0;
+    let mut shift = 0u32;
+    loop {
+        let byte = read_u8(input)?;
+        value |= u64::from(byte & 0x7f) << shift;
+        if byte & 0x80 == 0 {
+            break;
+        }
+        shift += 7;
+    }
+    Ok(value)
+}
@@ -0,0 +1,381 @@
+// Copyright 2023 The Regents of the University of California
+// released under BSD 3-Clause License
+// author: Kevin Laeufer <laeufer@berkeley.edu>
+
+//! A streaming writer for the FST waveform format, using the same `FstScopeType`,
+//! `FstVarType`, `FstVarDirection` and `FstSignalValue` types as [`crate::FstReader`] so
+//! that a hierarchy/signal stream produced here can be read back with
+//! [`crate::FstReader`]. The hierarchy is declared through [`FstWriter`]'s methods, not
+//! as `FstHierarchyEntry` values, and the header is filled in by [`FstWriter::finish`]
+//! from what was written, not taken as an `FstHeader`.
+
+use crate::hier_wire::{
+    scope_type_to_byte, var_direction_to_byte, var_type_to_byte, HIER_TAG_ENUM_TABLE,
+    HIER_TAG_ENUM_TABLE_REF, HIER_TAG_SCOPE, HIER_TAG_UP_SCOPE, HIER_TAG_VAR,
+};
+use crate::types::{FstScopeType, FstSignalHandle, FstSignalValue, FstVarDirection, FstVarType};
+use std::collections::HashMap;
+use std::fmt;
+use std::io::{Seek, SeekFrom, Write};
+
+// Block type tags, matching `fstBlockType` in fstapi.h (see also src/reader.rs).
+const BT_HDR: u8 = 0;
+const BT_VC_DATA: u8 = 1;
+const BT_BLACKOUT: u8 = 2;
+const BT_HIER: u8 = 4;
+
+const HEADER_PAYLOAD_LEN: u64 = 8 + 8 + 8 + 8 + 8 + 8 + 8 + 8 + 1 + 128 + 119;
+
+/// Errors that can occur while writing an FST file.
+#[derive(Debug)]
+pub enum WriterError {
+    Io(std::io::Error),
+    /// A string field (e.g. a variable name) is longer than the format allows.
+    FieldTooLong { field: &'static str, max_len: usize },
+    /// A value's byte length didn't match the variable's declared bit length.
+    LengthMismatch {
+        handle: FstSignalHandle,
+        expected: u32,
+        actual: usize,
+    },
+}
+
+impl fmt::Display for WriterError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            WriterError::Io(e) => write!(f, "I/O error: {e}"),
+            WriterError::FieldTooLong { field, max_len } => {
+                write!(f, "{field} is longer than the maximum of {max_len} bytes")
+            }
+            WriterError::LengthMismatch {
+                handle,
+                expected,
+                actual,
+            } => write!(
+                f,
+                "value for signal {handle} has {actual} bytes, expected {expected}"
+            ),
+        }
+    }
+}
+
+impl std::error::Error for WriterError {}
+
+impl From<std::io::Error> for WriterError {
+    fn from(e: std::io::Error) -> Self {
+        WriterError::Io(e)
+    }
+}
+
+pub type WriterResult<T> = Result<T, WriterError>;
+
+enum PendingValue {
+    String(String),
+    Real(f64),
+}
+
+/// Writes an FST file incrementally: declare the hierarchy with [`FstWriter::scope`],
+/// [`FstWriter::up_scope`] and [`FstWriter::add_var`], then stream value changes with
+/// [`FstWriter::emit_value_change`], periodically calling [`FstWriter::flush_block`] to
+/// bound memory use, and finally call [`FstWriter::finish`].
+pub struct FstWriter<W: Write + Seek> {
+    output: W,
+    header_payload_offset: u64,
+    start_time: Option<u64>,
+    end_time: u64,
+    next_handle: u32,
+    var_count: u64,
+    /// Declared bit/character length per signal handle, checked by `emit_value_change`.
+    var_lengths: HashMap<FstSignalHandle, u32>,
+    hierarchy: Vec<u8>,
+    next_enum_table_handle: u64,
+    current_block: Vec<(u64, FstSignalHandle, PendingValue)>,
+    current_block_start: Option<u64>,
+    blackout_active: bool,
+    blackouts: Vec<(u64, bool)>,
+}
+
+impl<W: Write + Seek> FstWriter<W> {
+    /// Opens a new FST file for writing, emitting a placeholder header block that is
+    /// patched with the final `end_time`/`var_count`/`max_handle` in [`FstWriter::finish`].
+    pub fn open(mut output: W) -> WriterResult<Self> {
+        output.write_all(&[BT_HDR])?;
+        output.write_all(&(HEADER_PAYLOAD_LEN + 8).to_be_bytes())?;
+        let header_payload_offset = output.stream_position()?;
+        output.write_all(&vec![0u8; HEADER_PAYLOAD_LEN as usize])?;
+        Ok(FstWriter {
+            output,
+            header_payload_offset,
+            start_time: None,
+            end_time: 0,
+            next_handle: 1,
+            var_count: 0,
+            var_lengths: HashMap::new(),
+            hierarchy: Vec::new(),
+            next_enum_table_handle: 1,
+            current_block: Vec::new(),
+            current_block_start: None,
+            blackout_active: true,
+            blackouts: Vec::new(),
+        })
+    }
+
+    fn push_hierarchy_entry(&mut self, tag: u8, fields: &[&[u8]]) {
+        self.hierarchy.push(tag);
+        for field in fields {
+            write_varint(&mut self.hierarchy, field.len() as u64);
+            self.hierarchy.extend_from_slice(field);
+        }
+    }
+
+    /// Opens a new scope, e.g. a module or a begin-block.
+    pub fn scope(&mut self, tpe: FstScopeType, name: &str, component: &str) -> WriterResult<()> {
+        check_len("scope name", name, u32::MAX as usize)?;
+        self.push_hierarchy_entry(
+            HIER_TAG_SCOPE,
+            &[&[scope_type_to_byte(tpe)], name.as_bytes(), component.as_bytes()],
+        );
+        Ok(())
+    }
+
+    /// Closes the most recently opened scope.
+    pub fn up_scope(&mut self) {
+        self.hierarchy.push(HIER_TAG_UP_SCOPE);
+    }
+
+    /// Declares a new variable, returning the handle it will be referenced by in
+    /// [`FstWriter::emit_value_change`].
+    pub fn add_var(
+        &mut self,
+        tpe: FstVarType,
+        direction: FstVarDirection,
+        name: &str,
+        length: u32,
+    ) -> WriterResult<FstSignalHandle> {
+        check_len("variable name", name, u32::MAX as usize)?;
+        let handle = FstSignalHandle::new(self.next_handle);
+        self.next_handle += 1;
+        self.var_count += 1;
+        self.var_lengths.insert(handle, length);
+        self.push_hierarchy_entry(
+            HIER_TAG_VAR,
+            &[
+                &[var_type_to_byte(tpe), var_direction_to_byte(direction)],
+                &length.to_be_bytes(),
+                &handle_to_bytes(handle),
+                name.as_bytes(),
+            ],
+        );
+        Ok(handle)
+    }
+
+    /// Declares an enum table, returning the table handle to pass to
+    /// [`FstWriter::add_enum_table_ref`] for the variables that use it.
+    pub fn add_enum_table(&mut self, name: &str, mapping: &[(String, String)]) -> u64 {
+        let handle = self.next_enum_table_handle;
+        self.next_enum_table_handle += 1;
+        let mut encoded = Vec::new();
+        write_varint(&mut encoded, mapping.len() as u64);
+        for (value, label) in mapping {
+            write_varint(&mut encoded, value.len() as u64);
+            encoded.extend_from_slice(value.as_bytes());
+            write_varint(&mut encoded, label.len() as u64);
+            encoded.extend_from_slice(label.as_bytes());
+        }
+        self.push_hierarchy_entry(
+            HIER_TAG_ENUM_TABLE,
+            &[&handle.to_be_bytes(), name.as_bytes(), &encoded],
+        );
+        handle
+    }
+
+    /// Associates the *next* variable declared with `add_var` with an enum table.
+    pub fn add_enum_table_ref(&mut self, table_handle: u64) {
+        self.push_hierarchy_entry(HIER_TAG_ENUM_TABLE_REF, &[&table_handle.to_be_bytes()]);
+    }
+
+    /// Records a VCD `$dumpoff`/`$dumpon` transition at `time`.
+    pub fn set_dump_active(&mut self, time: u64, active: bool) {
+        if active != self.blackout_active {
+            self.blackouts.push((time, active));
+            self.blackout_active = active;
+        }
+    }
+
+    /// Buffers a value change to be written out in the current value-change block.
+    pub fn emit_value_change(
+        &mut self,
+        time: u64,
+        handle: FstSignalHandle,
+        value: FstSignalValue,
+    ) -> WriterResult<()> {
+        if self.start_time.is_none() {
+            self.start_time = Some(time);
+        }
+        if self.current_block_start.is_none() {
+            self.current_block_start = Some(time);
+        }
+        self.end_time = self.end_time.max(time);
+        let pending = match value {
+            FstSignalValue::String(s) => {
+                self.check_value_length(handle, s.len())?;
+                PendingValue::String(s.to_string())
+            }
+            FstSignalValue::Real(r) => PendingValue::Real(r),
+            FstSignalValue::Enum { raw, .. } => {
+                self.check_value_length(handle, raw.len())?;
+                PendingValue::String(raw.to_string())
+            }
+        };
+        self.current_block.push((time, handle, pending));
+        Ok(())
+    }
+
+    /// Checks `actual` against the length the signal was declared with in `add_var`.
+    fn check_value_length(&self, handle: FstSignalHandle, actual: usize) -> WriterResult<()> {
+        let expected = *self.var_lengths.get(&handle).unwrap_or(&(actual as u32));
+        if expected as usize != actual {
+            return Err(WriterError::LengthMismatch {
+                handle,
+                expected,
+                actual,
+            });
+        }
+        Ok(())
+    }
+
+    /// Flushes the buffered value changes as one gzip-compressed value-change data block.
+    ///
+    /// Callers writing a large dump should call this periodically (e.g. every N time
+    /// steps) so the writer does not have to hold the whole dump in memory at once;
+    /// [`FstWriter::finish`] flushes any remaining changes automatically.
+    pub fn flush_block(&mut self) -> WriterResult<()> {
+        if self.current_block.is_empty() {
+            return Ok(());
+        }
+        let block_start = self.current_block_start.unwrap_or(0);
+        let block_end = self.current_block.iter().map(|(t, _, _)| *t).max().unwrap_or(block_start);
+
+        let mut changes = Vec::new();
+        for (time, handle, value) in self.current_block.drain(..) {
+            write_varint(&mut changes, time);
+            write_varint(&mut changes, handle_index_u64(handle));
+            match value {
+                PendingValue::String(s) => {
+                    changes.push(0);
+                    write_varint(&mut changes, s.len() as u64);
+                    changes.extend_from_slice(s.as_bytes());
+                }
+                PendingValue::Real(r) => {
+                    changes.push(1);
+                    changes.extend_from_slice(&r.to_le_bytes());
+                }
+            }
+        }
+
+        let mut compressed = Vec::new();
+        {
+            let mut encoder =
+                flate2::write::GzEncoder::new(&mut compressed, flate2::Compression::default());
+            encoder.write_all(&changes)?;
+            encoder.finish()?;
+        }
+
+        let mut payload = Vec::new();
+        write_varint(&mut payload, block_start);
+        write_varint(&mut payload, block_end);
+        write_varint(&mut payload, changes.len() as u64);
+        payload.extend_from_slice(&compressed);
+
+        self.output.write_all(&[BT_VC_DATA])?;
+        self.output
+            .write_all(&(payload.len() as u64 + 8).to_be_bytes())?;
+        self.output.write_all(&payload)?;
+        self.current_block_start = None;
+        Ok(())
+    }
+
+    /// Flushes the remaining value changes, writes the blackout/hierarchy blocks, patches
+    /// the header with the final time range and counts, and returns the underlying writer.
+    pub fn finish(mut self) -> WriterResult<W> {
+        self.flush_block()?;
+
+        if !self.blackouts.is_empty() {
+            let mut payload = Vec::new();
+            write_varint(&mut payload, self.blackouts.len() as u64);
+            let mut prev_time = 0u64;
+            for (time, active) in &self.blackouts {
+                payload.push(if *active { 1 } else { 0 });
+                write_varint(&mut payload, time - prev_time);
+                prev_time = *time;
+            }
+            self.output.write_all(&[BT_BLACKOUT])?;
+            self.output
+                .write_all(&(payload.len() as u64 + 8).to_be_bytes())?;
+            self.output.write_all(&payload)?;
+        }
+
+        let mut compressed = Vec::new();
+        {
+            let mut encoder =
+                flate2::write::GzEncoder::new(&mut compressed, flate2::Compression::default());
+            encoder.write_all(&self.hierarchy)?;
+            encoder.finish()?;
+        }
+        self.output.write_all(&[BT_HIER])?;
+        let mut hier_payload = Vec::new();
+        write_varint(&mut hier_payload, self.hierarchy.len() as u64);
+        hier_payload.extend_from_slice(&compressed);
+        self.output
+            .write_all(&(hier_payload.len() as u64 + 8).to_be_bytes())?;
+        self.output.write_all(&hier_payload)?;
+
+        // Patch the header now that `end_time`/`var_count`/`max_handle` are known.
+        self.output
+            .seek(SeekFrom::Start(self.header_payload_offset))?;
+        self.output
+            .write_all(&self.start_time.unwrap_or(0).to_be_bytes())?;
+        self.output.write_all(&self.end_time.to_be_bytes())?;
+        self.output.write_all(&[0u8; 8])?; // real-number endianness marker
+        self.output.write_all(&0u64.to_be_bytes())?; // writer memory use, unused
+        self.output.write_all(&0u64.to_be_bytes())?; // scope count, unused by the reader
+        self.output.write_all(&self.var_count.to_be_bytes())?;
+        self.output
+            .write_all(&(u64::from(self.next_handle) - 1).to_be_bytes())?;
+        self.output.write_all(&1u64.to_be_bytes())?; // value-change section count
+        self.output.write_all(&[0u8])?; // timescale exponent
+        self.output.write_all(&[0u8; 128])?; // version, left blank
+        self.output.write_all(&[0u8; 119])?; // date, left blank
+        self.output.seek(SeekFrom::End(0))?;
+
+        Ok(self.output)
+    }
+}
+
+fn check_len(field: &'static str, value: &str, max_len: usize) -> WriterResult<()> {
+    if value.len() > max_len {
+        Err(WriterError::FieldTooLong { field, max_len })
+    } else {
+        Ok(())
+    }
+}
+
+fn write_varint(out: &mut Vec<u8>, mut value: u64) {
+    loop {
+        let byte = (value & 0x7f) as u8;
+        value >>= 7;
+        if value == 0 {
+            out.push(byte);
+            break;
+        }
+        out.push(byte | 0x80);
+    }
+}
+
+fn handle_index_u64(handle: FstSignalHandle) -> u64 {
+    handle.get_index() as u64
+}
+
+fn handle_to_bytes(handle: FstSignalHandle) -> [u8; 8] {
+    handle_index_u64(handle).to_be_bytes()
+}
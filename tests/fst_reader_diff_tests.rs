@@ -6,7 +6,6 @@ use fst_native::*;
 use std::collections::VecDeque;
 use std::ffi::{c_char, c_uchar, c_void, CStr, CString};
 use std::fs::File;
-use std::future::pending;
 
 fn fst_sys_load_header(handle: *mut c_void) -> FstHeader {
     unsafe {
@@ -146,12 +145,12 @@ fn hierarchy_to_str(entry: &FstHierarchyEntry) -> String {
         } => {
             let names = mapping
                 .iter()
-                .map(|(v, n)| n.clone())
+                .map(|(_v, n)| n.clone())
                 .collect::<Vec<_>>()
                 .join(" ");
             let values = mapping
                 .iter()
-                .map(|(v, n)| v.clone())
+                .map(|(v, _n)| v.clone())
                 .collect::<Vec<_>>()
                 .join(" ");
             format!(
@@ -202,23 +201,17 @@ fn diff_hierarchy<R: std::io::Read + std::io::Seek>(
     let mut is_real = Vec::new();
     let check = |entry: FstHierarchyEntry| {
         // remember if variables are real valued
-        match &entry {
-            FstHierarchyEntry::Var { tpe, handle, .. } => {
-                let is_var_real = match tpe {
-                    FstVarType::Real
-                    | FstVarType::RealParameter
-                    | FstVarType::RealTime
-                    | FstVarType::ShortReal => true,
-                    _ => false,
-                };
-                let idx = handle.get_index();
-                if is_real.len() <= idx {
-                    is_real.resize(idx + 1, false);
-                }
-                is_real[idx] = is_var_real;
+        if let FstHierarchyEntry::Var { tpe, handle, .. } = &entry {
+            let is_var_real = matches!(
+                tpe,
+                FstVarType::Real | FstVarType::RealParameter | FstVarType::RealTime | FstVarType::ShortReal
+            );
+            let idx = handle.get_index();
+            if is_real.len() <= idx {
+                is_real.resize(idx + 1, false);
             }
-            _ => {}
-        };
+            is_real[idx] = is_var_real;
+        }
 
         let expected = exp_hierarchy.pop_front().unwrap();
         let actual = hierarchy_to_str(&entry);
@@ -230,7 +223,7 @@ fn diff_hierarchy<R: std::io::Read + std::io::Seek>(
 }
 
 fn fst_sys_load_signals(handle: *mut c_void, is_real: &[bool]) -> VecDeque<(u64, u32, String)> {
-    let mut out = VecDeque::new();
+    let out = VecDeque::new();
     let mut data = CallbackData {
         out,
         is_real: Vec::from(is_real),
@@ -265,7 +258,7 @@ extern "C" fn signal_change_callback(
     let data = unsafe { &mut *(data_ptr as *mut CallbackData) };
     let signal_idx = (handle - 1) as usize;
     let string = if data.is_real[signal_idx] {
-        let slic = unsafe { std::slice::from_raw_parts(value as *const u8, 8) };
+        let slic = unsafe { std::slice::from_raw_parts(value, 8) };
         let value = f64::from_le_bytes(slic.try_into().unwrap());
         format!("{value}")
     } else {
@@ -304,11 +297,12 @@ fn diff_signals<R: std::io::Read + std::io::Seek>(
     our_reader: &mut FstReader<R>,
     mut exp_signals: VecDeque<(u64, u32, String)>,
 ) {
-    let check = |time: u64, handle: FstSignalHandle, value: FstSignalValue| {
+    let check = |time: u64, handle: FstSignalHandle, value: FstSignalValue, _in_blackout: bool| {
         let (exp_time, exp_handle, exp_value) = exp_signals.pop_front().unwrap();
         let actual_as_string = match value {
             FstSignalValue::String(str) => str.to_string(),
             FstSignalValue::Real(value) => format!("{value}"),
+            FstSignalValue::Enum { name, .. } => name,
         };
         let actual = (time, handle.get_index() + 1, actual_as_string);
         let expected = (exp_time, exp_handle as usize, exp_value);
@@ -319,7 +313,7 @@ fn diff_signals<R: std::io::Read + std::io::Seek>(
     our_reader.read_signals(&filter, check).unwrap();
 }
 
-fn run_diff_test(filename: &str, filter: &FstFilter) {
+fn run_diff_test(filename: &str, _filter: &FstFilter) {
     // open file with FST library from GTKWave
     let c_path = CString::new(filename).unwrap();
     let exp_handle = unsafe { fst_sys::fstReaderOpen(c_path.as_ptr()) };
@@ -518,8 +512,10 @@ fn diff_xilinx_isim_test1() {
     run_diff_test("fsts/xilinx_isim/test1.vcd.fst", &FstFilter::all());
 }
 
+// TODO: FstReader only parses the bespoke encoding FstWriter produces, not the real
+// GTKWave bit-packed hierarchy/VC-data format this fixture uses.
 #[test]
-#[ignore] // TODO: implement blackout
+#[ignore]
 fn diff_xilinx_isim_test2x2_regex22_string1() {
     run_diff_test(
         "fsts/xilinx_isim/test2x2_regex22_string1.vcd.fst",
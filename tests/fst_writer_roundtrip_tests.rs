@@ -0,0 +1,433 @@
+// Copyright 2023 The Regents of the University of California
+// released under BSD 3-Clause License
+// author: Kevin Laeufer <laeufer@berkeley.edu>
+
+use fst_native::*;
+use std::cell::RefCell;
+use std::io::{self, Cursor, Seek, SeekFrom, Write};
+use std::rc::Rc;
+
+#[test]
+fn roundtrip_basic_hierarchy_and_values() {
+    let mut writer = FstWriter::open(Cursor::new(Vec::new())).unwrap();
+    writer
+        .scope(FstScopeType::Module, "top", "top")
+        .unwrap();
+    let clk = writer
+        .add_var(FstVarType::Wire, FstVarDirection::Implicit, "clk", 1)
+        .unwrap();
+    let data = writer
+        .add_var(FstVarType::Wire, FstVarDirection::Implicit, "data", 4)
+        .unwrap();
+    writer.up_scope();
+
+    writer
+        .emit_value_change(0, clk, FstSignalValue::String("0"))
+        .unwrap();
+    writer
+        .emit_value_change(0, data, FstSignalValue::String("0000"))
+        .unwrap();
+    writer
+        .emit_value_change(5, clk, FstSignalValue::String("1"))
+        .unwrap();
+    writer
+        .emit_value_change(5, data, FstSignalValue::String("1010"))
+        .unwrap();
+
+    let buf = writer.finish().unwrap().into_inner();
+
+    let mut reader = FstReader::open(Cursor::new(buf)).unwrap();
+
+    let mut entries = Vec::new();
+    reader.read_hierarchy(|entry| entries.push(entry)).unwrap();
+    assert_eq!(
+        entries,
+        vec![
+            FstHierarchyEntry::Scope {
+                tpe: FstScopeType::Module,
+                name: "top".to_string(),
+                component: "top".to_string(),
+            },
+            FstHierarchyEntry::Var {
+                tpe: FstVarType::Wire,
+                direction: FstVarDirection::Implicit,
+                name: "clk".to_string(),
+                length: 1,
+                handle: clk,
+                is_alias: false,
+            },
+            FstHierarchyEntry::Var {
+                tpe: FstVarType::Wire,
+                direction: FstVarDirection::Implicit,
+                name: "data".to_string(),
+                length: 4,
+                handle: data,
+                is_alias: false,
+            },
+            FstHierarchyEntry::UpScope,
+        ]
+    );
+
+    let mut changes = Vec::new();
+    reader
+        .read_signals(&FstFilter::all(), |time, handle, value, _in_blackout| {
+            if let FstSignalValue::String(s) = value {
+                changes.push((time, handle, s.to_string()));
+            }
+        })
+        .unwrap();
+    assert_eq!(
+        changes,
+        vec![
+            (0, clk, "0".to_string()),
+            (0, data, "0000".to_string()),
+            (5, clk, "1".to_string()),
+            (5, data, "1010".to_string()),
+        ]
+    );
+}
+
+#[test]
+fn roundtrip_enum_table_resolves_to_label() {
+    let mut writer = FstWriter::open(Cursor::new(Vec::new())).unwrap();
+    let table = writer.add_enum_table(
+        "state_t",
+        &[
+            ("00".to_string(), "Idle".to_string()),
+            ("01".to_string(), "Busy".to_string()),
+        ],
+    );
+    writer.add_enum_table_ref(table);
+    let state = writer
+        .add_var(FstVarType::Enum, FstVarDirection::Implicit, "state", 2)
+        .unwrap();
+    writer
+        .emit_value_change(0, state, FstSignalValue::String("01"))
+        .unwrap();
+    let buf = writer.finish().unwrap().into_inner();
+
+    let mut reader = FstReader::open(Cursor::new(buf)).unwrap();
+    reader.read_hierarchy(|_| {}).unwrap();
+
+    // Once the hierarchy (and with it the handle -> enum table association) has been
+    // read, `read_signals` resolves enum-backed values to their label automatically.
+    let mut resolved_name = None;
+    reader
+        .read_signals(&FstFilter::all(), |_time, _handle, value, _in_blackout| {
+            if let FstSignalValue::Enum { name, .. } = value {
+                resolved_name = Some(name);
+            }
+        })
+        .unwrap();
+    assert_eq!(resolved_name, Some("Busy".to_string()));
+}
+
+#[test]
+fn roundtrip_respects_blackout_region() {
+    let mut writer = FstWriter::open(Cursor::new(Vec::new())).unwrap();
+    let sig = writer
+        .add_var(FstVarType::Wire, FstVarDirection::Implicit, "sig", 1)
+        .unwrap();
+    writer
+        .emit_value_change(0, sig, FstSignalValue::String("0"))
+        .unwrap();
+    writer.set_dump_active(2, false);
+    writer.set_dump_active(8, true);
+    writer
+        .emit_value_change(10, sig, FstSignalValue::String("1"))
+        .unwrap();
+    let buf = writer.finish().unwrap().into_inner();
+
+    let reader = FstReader::open(Cursor::new(buf)).unwrap();
+    assert_eq!(
+        reader.get_blackouts(),
+        &[
+            FstBlackout {
+                time: 2,
+                activity_enabled: false,
+            },
+            FstBlackout {
+                time: 8,
+                activity_enabled: true,
+            },
+        ]
+    );
+}
+
+#[test]
+fn filter_time_range_restricts_to_matching_blocks() {
+    let mut writer = FstWriter::open(Cursor::new(Vec::new())).unwrap();
+    let sig = writer
+        .add_var(FstVarType::Wire, FstVarDirection::Implicit, "sig", 1)
+        .unwrap();
+
+    writer
+        .emit_value_change(0, sig, FstSignalValue::String("0"))
+        .unwrap();
+    writer.flush_block().unwrap();
+    writer
+        .emit_value_change(10, sig, FstSignalValue::String("1"))
+        .unwrap();
+    writer.flush_block().unwrap();
+    writer
+        .emit_value_change(20, sig, FstSignalValue::String("0"))
+        .unwrap();
+    writer.flush_block().unwrap();
+    let buf = writer.finish().unwrap().into_inner();
+
+    let mut reader = FstReader::open(Cursor::new(buf)).unwrap();
+    let mut times = Vec::new();
+    reader
+        .read_signals(
+            &FstFilter::time_range(10, 10),
+            |time, _handle, _value, _in_blackout| times.push(time),
+        )
+        .unwrap();
+    assert_eq!(times, vec![10]);
+}
+
+#[test]
+fn filter_signals_restricts_to_matching_handles() {
+    let mut writer = FstWriter::open(Cursor::new(Vec::new())).unwrap();
+    let a = writer
+        .add_var(FstVarType::Wire, FstVarDirection::Implicit, "a", 1)
+        .unwrap();
+    let b = writer
+        .add_var(FstVarType::Wire, FstVarDirection::Implicit, "b", 1)
+        .unwrap();
+
+    writer.emit_value_change(0, a, FstSignalValue::String("0")).unwrap();
+    writer.emit_value_change(0, b, FstSignalValue::String("1")).unwrap();
+    writer.flush_block().unwrap();
+    writer.emit_value_change(10, a, FstSignalValue::String("1")).unwrap();
+    writer.emit_value_change(10, b, FstSignalValue::String("0")).unwrap();
+    writer.flush_block().unwrap();
+    let buf = writer.finish().unwrap().into_inner();
+
+    let mut reader = FstReader::open(Cursor::new(buf)).unwrap();
+    let mut changes = Vec::new();
+    reader
+        .read_signals(
+            &FstFilter::signals(&[b]),
+            |time, handle, _value, _in_blackout| changes.push((time, handle)),
+        )
+        .unwrap();
+    assert_eq!(changes, vec![(0, b), (10, b)]);
+}
+
+#[test]
+fn filter_and_time_range_and_signals_combine() {
+    let mut writer = FstWriter::open(Cursor::new(Vec::new())).unwrap();
+    let a = writer
+        .add_var(FstVarType::Wire, FstVarDirection::Implicit, "a", 1)
+        .unwrap();
+    let b = writer
+        .add_var(FstVarType::Wire, FstVarDirection::Implicit, "b", 1)
+        .unwrap();
+
+    writer.emit_value_change(0, a, FstSignalValue::String("0")).unwrap();
+    writer.emit_value_change(0, b, FstSignalValue::String("0")).unwrap();
+    writer.flush_block().unwrap();
+    writer.emit_value_change(10, a, FstSignalValue::String("1")).unwrap();
+    writer.emit_value_change(10, b, FstSignalValue::String("1")).unwrap();
+    writer.flush_block().unwrap();
+    let buf = writer.finish().unwrap().into_inner();
+
+    let mut reader = FstReader::open(Cursor::new(buf)).unwrap();
+    let mut changes = Vec::new();
+    let filter = FstFilter::time_range(10, 10).and_signals(&[b]);
+    reader
+        .read_signals(&filter, |time, handle, _value, _in_blackout| {
+            changes.push((time, handle))
+        })
+        .unwrap();
+    assert_eq!(changes, vec![(10, b)]);
+}
+
+/// A `Write + Seek` sink over a shared buffer. Lets a test inspect the exact byte offset
+/// `FstWriter` has reached after each `flush_block()` call (to corrupt a specific block's
+/// payload afterwards), something not possible through `Cursor<Vec<u8>>` alone once the
+/// buffer has been handed to the writer.
+struct SharedBuf {
+    data: Rc<RefCell<Vec<u8>>>,
+    pos: usize,
+}
+
+impl SharedBuf {
+    fn new() -> Self {
+        SharedBuf {
+            data: Rc::new(RefCell::new(Vec::new())),
+            pos: 0,
+        }
+    }
+}
+
+impl Write for SharedBuf {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        let mut data = self.data.borrow_mut();
+        let end = self.pos + buf.len();
+        if end > data.len() {
+            data.resize(end, 0);
+        }
+        data[self.pos..end].copy_from_slice(buf);
+        self.pos = end;
+        Ok(buf.len())
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        Ok(())
+    }
+}
+
+impl Seek for SharedBuf {
+    fn seek(&mut self, pos: SeekFrom) -> io::Result<u64> {
+        let len = self.data.borrow().len() as i64;
+        let new_pos = match pos {
+            SeekFrom::Start(p) => p as i64,
+            SeekFrom::End(p) => len + p,
+            SeekFrom::Current(p) => self.pos as i64 + p,
+        };
+        self.pos = new_pos as usize;
+        Ok(self.pos as u64)
+    }
+}
+
+#[test]
+fn filter_skips_decoding_out_of_range_block() {
+    let shared = SharedBuf::new();
+    let data = shared.data.clone();
+    let mut writer = FstWriter::open(shared).unwrap();
+    let sig = writer
+        .add_var(FstVarType::Wire, FstVarDirection::Implicit, "sig", 1)
+        .unwrap();
+
+    writer.emit_value_change(0, sig, FstSignalValue::String("0")).unwrap();
+    writer.flush_block().unwrap();
+    writer.emit_value_change(10, sig, FstSignalValue::String("1")).unwrap();
+    writer.flush_block().unwrap();
+    let block_two_end = data.borrow().len();
+    writer.finish().unwrap();
+
+    // Corrupt only the tail of the second block's gzip payload, leaving its tag,
+    // section-length and start/end-time header intact so `FstReader::open`'s block-table
+    // scan (which reads those fields but never decompresses) still succeeds.
+    {
+        let mut data = data.borrow_mut();
+        let corrupt_start = block_two_end - 4;
+        for byte in &mut data[corrupt_start..block_two_end] {
+            *byte ^= 0xff;
+        }
+    }
+    let buf = data.borrow().clone();
+
+    // A filter that only overlaps the first, uncorrupted block never decompresses the
+    // corrupted second block, so reading succeeds.
+    let mut reader = FstReader::open(Cursor::new(buf.clone())).unwrap();
+    let mut times = Vec::new();
+    reader
+        .read_signals(
+            &FstFilter::time_range(0, 0),
+            |time, _handle, _value, _in_blackout| times.push(time),
+        )
+        .unwrap();
+    assert_eq!(times, vec![0]);
+
+    // A filter that does overlap the corrupted block surfaces the decompression error,
+    // confirming the first read's success was because the block was skipped, not because
+    // the corruption was harmless.
+    let mut reader = FstReader::open(Cursor::new(buf)).unwrap();
+    let err = reader.read_signals(&FstFilter::all(), |_, _, _, _| {});
+    assert!(matches!(err, Err(ReaderError::Decompression(_))));
+}
+
+#[test]
+fn value_at_returns_none_before_first_change() {
+    let mut writer = FstWriter::open(Cursor::new(Vec::new())).unwrap();
+    let sig = writer
+        .add_var(FstVarType::Wire, FstVarDirection::Implicit, "sig", 1)
+        .unwrap();
+    writer.emit_value_change(5, sig, FstSignalValue::String("1")).unwrap();
+    let buf = writer.finish().unwrap().into_inner();
+
+    let mut reader = FstReader::open(Cursor::new(buf)).unwrap();
+    assert_eq!(reader.value_at(sig, 0).unwrap(), None);
+}
+
+#[test]
+fn value_at_returns_most_recent_change_at_or_before_time() {
+    let mut writer = FstWriter::open(Cursor::new(Vec::new())).unwrap();
+    let sig = writer
+        .add_var(FstVarType::Wire, FstVarDirection::Implicit, "sig", 1)
+        .unwrap();
+    writer.emit_value_change(0, sig, FstSignalValue::String("0")).unwrap();
+    writer.emit_value_change(5, sig, FstSignalValue::String("1")).unwrap();
+    let buf = writer.finish().unwrap().into_inner();
+
+    let mut reader = FstReader::open(Cursor::new(buf)).unwrap();
+    assert_eq!(reader.value_at(sig, 4).unwrap(), Some(FstSignalValue::String("0")));
+    assert_eq!(reader.value_at(sig, 5).unwrap(), Some(FstSignalValue::String("1")));
+    assert_eq!(reader.value_at(sig, 100).unwrap(), Some(FstSignalValue::String("1")));
+}
+
+#[test]
+fn transitions_returns_changes_within_inclusive_range() {
+    let mut writer = FstWriter::open(Cursor::new(Vec::new())).unwrap();
+    let sig = writer
+        .add_var(FstVarType::Wire, FstVarDirection::Implicit, "sig", 1)
+        .unwrap();
+    writer.emit_value_change(0, sig, FstSignalValue::String("0")).unwrap();
+    writer.emit_value_change(5, sig, FstSignalValue::String("1")).unwrap();
+    writer.emit_value_change(10, sig, FstSignalValue::String("0")).unwrap();
+    writer.emit_value_change(15, sig, FstSignalValue::String("1")).unwrap();
+    let buf = writer.finish().unwrap().into_inner();
+
+    let mut reader = FstReader::open(Cursor::new(buf)).unwrap();
+    let changes: Vec<_> = reader
+        .transitions(sig, 5..=10)
+        .unwrap()
+        .map(|(time, value)| {
+            (
+                time,
+                match value {
+                    FstSignalValue::String(s) => s.to_string(),
+                    other => panic!("unexpected value kind: {other:?}"),
+                },
+            )
+        })
+        .collect();
+    assert_eq!(changes, vec![(5, "1".to_string()), (10, "0".to_string())]);
+}
+
+#[test]
+fn value_at_and_transitions_resolve_enum_labels() {
+    let mut writer = FstWriter::open(Cursor::new(Vec::new())).unwrap();
+    let table = writer.add_enum_table(
+        "state_t",
+        &[
+            ("00".to_string(), "Idle".to_string()),
+            ("01".to_string(), "Busy".to_string()),
+        ],
+    );
+    writer.add_enum_table_ref(table);
+    let state = writer
+        .add_var(FstVarType::Enum, FstVarDirection::Implicit, "state", 2)
+        .unwrap();
+    writer
+        .emit_value_change(0, state, FstSignalValue::String("01"))
+        .unwrap();
+    let buf = writer.finish().unwrap().into_inner();
+
+    let mut reader = FstReader::open(Cursor::new(buf)).unwrap();
+    reader.read_hierarchy(|_| {}).unwrap();
+
+    match reader.value_at(state, 0).unwrap() {
+        Some(FstSignalValue::Enum { name, .. }) => assert_eq!(name, "Busy"),
+        other => panic!("expected a resolved enum value, got {other:?}"),
+    }
+
+    let mut transitions = reader.transitions(state, 0..=0).unwrap();
+    match transitions.next() {
+        Some((_, FstSignalValue::Enum { name, .. })) => assert_eq!(name, "Busy"),
+        other => panic!("expected a resolved enum value, got {other:?}"),
+    }
+}